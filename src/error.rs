@@ -0,0 +1,44 @@
+//! Errors that can be returned by this crate.
+
+use core::fmt;
+
+/// An error returned by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The given address is not aligned to the required boundary.
+    NotAligned {
+        /// The required alignment, in bytes. Always a power of 2.
+        alignment: usize,
+        /// The address which violated the alignment requirement.
+        address: usize,
+    },
+    /// `caplength` is smaller than `0x20`, the size of the Capability Registers themselves.
+    CapabilityRegistersLengthTooSmall(u8),
+    /// The Doorbell Offset and the Runtime Register Space Offset are identical.
+    RegistersAtSameOffset,
+    /// `hcsparams1.number_of_device_slots()` is `0`.
+    NoDeviceSlots,
+    /// The Extended Capability List was not terminated within
+    /// [`MAX_EXTENDED_CAPABILITIES`](crate::extended_capabilities::MAX_EXTENDED_CAPABILITIES)
+    /// entries, suggesting a malformed or adversarial Next Capability Pointer chain.
+    TooManyExtendedCapabilities,
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAligned { alignment, address } => {
+                write!(f, "address {address:#x} is not aligned to {alignment} bytes")
+            }
+            Self::CapabilityRegistersLengthTooSmall(l) => {
+                write!(f, "caplength {l:#x} is smaller than the Capability Registers block itself")
+            }
+            Self::RegistersAtSameOffset => {
+                write!(f, "the Doorbell Array and Runtime Registers start at the same offset")
+            }
+            Self::NoDeviceSlots => write!(f, "the xHC reports 0 available device slots"),
+            Self::TooManyExtendedCapabilities => {
+                write!(f, "the Extended Capability List did not terminate within the maximum number of entries")
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! xHCI Extended Capabilities.
+//!
+//! The xHCI Extended Capability List is a singly linked list that starts at
+//! `(MMIO base) + (xECP << 2)`, where `xECP` is the value returned by
+//! [`CapabilityParameters1::xhci_extended_capabilities_pointer`]. Each entry begins with a
+//! 32-bit header: bits 0..=7 are the Capability ID and bits 8..=15 are the Next Capability
+//! Pointer, a dword offset from the current entry to the next one. A Next Capability Pointer of
+//! 0 terminates the list.
+//!
+//! Nothing enforces that a real xHC actually terminates the chain, so [`List`] gives up and
+//! returns [`Error::TooManyExtendedCapabilities`] after [`MAX_EXTENDED_CAPABILITIES`] entries
+//! rather than walking an adversarial or malformed chain forever.
+//!
+//! [`CapabilityParameters1::xhci_extended_capabilities_pointer`]: crate::registers::capability::CapabilityParameters1::xhci_extended_capabilities_pointer
+
+use crate::{accessor::Accessor, error::Error, mapper::Mapper};
+use bit_field::BitField;
+use core::convert::TryInto;
+
+/// Capability ID of the USB Legacy Support Capability.
+const USB_LEGACY_SUPPORT_ID: u8 = 1;
+/// Capability ID of the xHCI Supported Protocol Capability.
+const SUPPORTED_PROTOCOL_ID: u8 = 2;
+
+/// The maximum number of entries [`List`] will follow before giving up on the Next Capability
+/// Pointer chain and returning [`Error::TooManyExtendedCapabilities`].
+///
+/// The xHCI Extended Capability List has no hardware-enforced length, so a malformed or
+/// adversarial xHC could otherwise make the chain of Next Capability Pointers run on forever.
+/// `u8::MAX` is a generous bound: no real xHC is known to implement anywhere close to this many
+/// extended capabilities.
+pub const MAX_EXTENDED_CAPABILITIES: usize = u8::MAX as usize;
+
+/// An iterator over the entries of the xHCI Extended Capability List.
+///
+/// Each call to [`Iterator::next`] reads the header of the current entry, decodes it into an
+/// [`ExtendedCapability`], and follows the Next Capability Pointer to advance.
+pub struct List<M>
+where
+    M: Mapper + Clone,
+{
+    current: Option<usize>,
+    mapper: M,
+    visited: usize,
+}
+impl<M> List<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new `List`.
+    ///
+    /// `mmio_base` must be the base address of the MMIO region, and `xecp` must be the value
+    /// returned by [`CapabilityParameters1::xhci_extended_capabilities_pointer`]. Returns `None`
+    /// if `xecp` is 0, meaning the xHC does not implement the Extended Capability List.
+    ///
+    /// [`CapabilityParameters1::xhci_extended_capabilities_pointer`]: crate::registers::capability::CapabilityParameters1::xhci_extended_capabilities_pointer
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that only one `List` is created for the xHC, otherwise undefined
+    /// behaviors such as data races may occur.
+    #[must_use]
+    pub unsafe fn new(mmio_base: usize, xecp: u16, mapper: M) -> Option<Self> {
+        if xecp == 0 {
+            None
+        } else {
+            Some(Self {
+                current: Some(mmio_base + (usize::from(xecp) << 2)),
+                mapper,
+                visited: 0,
+            })
+        }
+    }
+}
+impl<M> Iterator for List<M>
+where
+    M: Mapper + Clone,
+{
+    type Item = Result<ExtendedCapability<M>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let addr = self.current?;
+
+        if self.visited >= MAX_EXTENDED_CAPABILITIES {
+            self.current = None;
+            return Some(Err(Error::TooManyExtendedCapabilities));
+        }
+        self.visited += 1;
+
+        // SAFETY: `addr` points to the head of a valid xECP list entry.
+        let header: Accessor<Header, M> = match unsafe { Accessor::new(addr, 0, self.mapper.clone()) } {
+            Ok(h) => h,
+            Err(e) => {
+                self.current = None;
+                return Some(Err(e));
+            }
+        };
+
+        let id = header.capability_id();
+        let next = header.next_capability_pointer();
+        drop(header);
+
+        self.current = if next == 0 {
+            None
+        } else {
+            Some(addr + (usize::from(next) << 2))
+        };
+
+        // SAFETY: `addr` points to the head of a valid xECP list entry, whose first dword we
+        // have just read as `id`.
+        Some(unsafe { ExtendedCapability::parse(addr, id, self.mapper.clone()) })
+    }
+}
+
+/// A single, typed entry of the xHCI Extended Capability List.
+pub enum ExtendedCapability<M>
+where
+    M: Mapper + Clone,
+{
+    /// USB Legacy Support Capability (Capability ID 1).
+    UsbLegacySupport(Accessor<UsbLegacySupport, M>),
+    /// xHCI Supported Protocol Capability (Capability ID 2).
+    SupportedProtocol(Accessor<SupportedProtocol, M>),
+    /// A capability whose ID this crate does not yet decode.
+    Unknown(u8),
+}
+impl<M> ExtendedCapability<M>
+where
+    M: Mapper + Clone,
+{
+    unsafe fn parse(addr: usize, id: u8, mapper: M) -> Result<Self, Error> {
+        Ok(match id {
+            USB_LEGACY_SUPPORT_ID => Self::UsbLegacySupport(Accessor::new(addr, 0, mapper)?),
+            SUPPORTED_PROTOCOL_ID => Self::SupportedProtocol(Accessor::new(addr, 0, mapper)?),
+            id => Self::Unknown(id),
+        })
+    }
+}
+
+/// The header common to every entry of the xHCI Extended Capability List.
+#[repr(transparent)]
+struct Header(u32);
+impl Header {
+    fn capability_id(&self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    fn next_capability_pointer(&self) -> u8 {
+        self.0.get_bits(8..=15).try_into().unwrap()
+    }
+}
+
+/// USB Legacy Support Capability.
+#[repr(C)]
+pub struct UsbLegacySupport {
+    /// USB Legacy Support Capability (USBLEGSUP) dword.
+    pub usblegsup: UsbLegacySupportCapability,
+    /// USB Legacy Support Control/Status (USBLEGCTLSTS) dword.
+    pub usblegctlsts: UsbLegacySupportControlStatus,
+}
+
+/// USB Legacy Support Capability (USBLEGSUP) dword.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct UsbLegacySupportCapability(u32);
+impl UsbLegacySupportCapability {
+    /// Returns the value of the Capability ID field.
+    #[must_use]
+    pub fn capability_id(&self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the HC BIOS Owned Semaphore bit.
+    #[must_use]
+    pub fn hc_bios_owned_semaphore(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Sets the value of the HC BIOS Owned Semaphore bit.
+    pub fn set_hc_bios_owned_semaphore(&mut self, owned: bool) -> &mut Self {
+        self.0.set_bit(16, owned);
+        self
+    }
+
+    /// Returns the value of the HC OS Owned Semaphore bit.
+    #[must_use]
+    pub fn hc_os_owned_semaphore(&self) -> bool {
+        self.0.get_bit(24)
+    }
+
+    /// Sets the value of the HC OS Owned Semaphore bit.
+    pub fn set_hc_os_owned_semaphore(&mut self, owned: bool) -> &mut Self {
+        self.0.set_bit(24, owned);
+        self
+    }
+}
+
+/// USB Legacy Support Control/Status (USBLEGCTLSTS) dword.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct UsbLegacySupportControlStatus(u32);
+impl UsbLegacySupportControlStatus {
+    /// Returns the raw value of the register.
+    #[must_use]
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// Sets the raw value of the register.
+    pub fn set(&mut self, v: u32) -> &mut Self {
+        self.0 = v;
+        self
+    }
+}
+
+/// xHCI Supported Protocol Capability.
+///
+/// Maps a contiguous range of root hub ports (Compatible Port Offset/Count) to a USB revision,
+/// letting callers pick the right value to pass to [`EnableSlot::set_slot_type`].
+///
+/// [`EnableSlot::set_slot_type`]: crate::ring::trb::command::EnableSlot::set_slot_type
+#[repr(C)]
+pub struct SupportedProtocol {
+    /// Dword 0: Capability ID, Next Capability Pointer, and Revision Major/Minor.
+    pub dword0: SupportedProtocolDword0,
+    /// Dword 1: Name String (e.g. `"USB "`).
+    pub name_string: NameString,
+    /// Dword 2: Compatible Port Offset and Compatible Port Count.
+    pub dword2: SupportedProtocolDword2,
+    /// Dword 3: Protocol Slot Type.
+    pub dword3: SupportedProtocolDword3,
+}
+
+/// Dword 0 of the xHCI Supported Protocol Capability.
+#[repr(transparent)]
+pub struct SupportedProtocolDword0(u32);
+impl SupportedProtocolDword0 {
+    /// Returns the value of the Capability ID field.
+    #[must_use]
+    pub fn capability_id(&self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the Revision Minor field.
+    #[must_use]
+    pub fn revision_minor(&self) -> u8 {
+        self.0.get_bits(16..=23).try_into().unwrap()
+    }
+
+    /// Returns the value of the Revision Major field.
+    #[must_use]
+    pub fn revision_major(&self) -> u8 {
+        self.0.get_bits(24..=31).try_into().unwrap()
+    }
+}
+
+/// Dword 1 of the xHCI Supported Protocol Capability: the Name String.
+#[repr(transparent)]
+pub struct NameString(u32);
+impl NameString {
+    /// Returns the four ASCII characters of the Name String, in the order they appear on the
+    /// wire (e.g. `[b'U', b'S', b'B', b' ']`).
+    #[must_use]
+    pub fn get(&self) -> [u8; 4] {
+        self.0.to_le_bytes()
+    }
+}
+impl core::fmt::Debug for NameString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("NameString")
+            .field(&core::str::from_utf8(&self.get()).unwrap_or("<invalid>"))
+            .finish()
+    }
+}
+
+/// Dword 2 of the xHCI Supported Protocol Capability.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct SupportedProtocolDword2(u32);
+impl SupportedProtocolDword2 {
+    /// Returns the value of the Compatible Port Offset field.
+    #[must_use]
+    pub fn compatible_port_offset(&self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the Compatible Port Count field.
+    #[must_use]
+    pub fn compatible_port_count(&self) -> u8 {
+        self.0.get_bits(8..=15).try_into().unwrap()
+    }
+}
+
+/// Dword 3 of the xHCI Supported Protocol Capability.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct SupportedProtocolDword3(u32);
+impl SupportedProtocolDword3 {
+    /// Returns the value of the Protocol Slot Type field.
+    ///
+    /// Pass this value to [`EnableSlot::set_slot_type`] when enabling a slot for a device
+    /// attached to one of this capability's compatible ports.
+    ///
+    /// [`EnableSlot::set_slot_type`]: crate::ring::trb::command::EnableSlot::set_slot_type
+    #[must_use]
+    pub fn protocol_slot_type(&self) -> u8 {
+        self.0.get_bits(0..=4).try_into().unwrap()
+    }
+}
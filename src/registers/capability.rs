@@ -10,7 +10,8 @@ pub struct Capability {
     /// Capability Registers Length
     pub caplength: CapabilityRegistersLength,
     _rsvd: u8,
-    _hciversion: u16,
+    /// Host Controller Interface Version Number
+    pub hciversion: HciVersion,
     /// Structural Parameters 1
     pub hcsparams1: StructuralParameters1,
     /// Structural Parameters 2
@@ -42,6 +43,60 @@ impl Capability {
     {
         Accessor::new(mmio_base, 0, mapper)
     }
+
+    /// Checks that the register block looks sane, returning a descriptive [`Error`] for the
+    /// first problem found.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`Error`] if:
+    /// * [`Self::caplength`] is smaller than `0x20`, the size of the Capability Registers
+    ///   themselves.
+    /// * [`Self::dboff`] is not dword-aligned, or [`Self::rtsoff`] is not 32-byte aligned (its
+    ///   reserved bits are RsvdZ down to bit 4, not bit 1 like [`Self::dboff`]).
+    /// * [`Self::dboff`] and [`Self::rtsoff`] are identical. Note that this only catches the two
+    ///   regions starting at the same offset; this register block does not carry enough size
+    ///   information (the Doorbell Array depends on the number of enabled device slots, and the
+    ///   Runtime Register space depends on the number of enabled interrupters) to detect partial
+    ///   overlap between regions that start at different offsets.
+    /// * [`Self::hcsparams1`]`.number_of_device_slots()` is `0`.
+    ///
+    /// [`Self::caplength`]: Capability::caplength
+    /// [`Self::dboff`]: Capability::dboff
+    /// [`Self::rtsoff`]: Capability::rtsoff
+    /// [`Self::hcsparams1`]: Capability::hcsparams1
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.caplength.get() < 0x20 {
+            return Err(Error::CapabilityRegistersLengthTooSmall(
+                self.caplength.get(),
+            ));
+        }
+
+        let dboff = self.dboff.get();
+        let rtsoff = self.rtsoff.get();
+
+        if dboff % 4 != 0 {
+            return Err(Error::NotAligned {
+                alignment: 4,
+                address: dboff as usize,
+            });
+        }
+        if rtsoff % 32 != 0 {
+            return Err(Error::NotAligned {
+                alignment: 32,
+                address: rtsoff as usize,
+            });
+        }
+        if dboff == rtsoff {
+            return Err(Error::RegistersAtSameOffset);
+        }
+
+        if self.hcsparams1.number_of_device_slots() == 0 {
+            return Err(Error::NoDeviceSlots);
+        }
+
+        Ok(())
+    }
 }
 
 /// Capability Registers Length
@@ -57,6 +112,27 @@ impl CapabilityRegistersLength {
     }
 }
 
+/// Host Controller Interface Version Number
+///
+/// The value is BCD-encoded, e.g. `0x0100` is version 1.0.0 and `0x0110` is version 1.1.0.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct HciVersion(u16);
+impl HciVersion {
+    /// Returns the major version number.
+    #[must_use]
+    pub fn major(&self) -> u8 {
+        self.0.get_bits(8..=15).try_into().unwrap()
+    }
+
+    /// Returns the minor version number as a BCD-encoded byte holding both the minor and
+    /// sub-minor digits, e.g. `0x10` for version 1.1.0.
+    #[must_use]
+    pub fn minor(&self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+}
+
 /// Structural Parameters 1
 #[repr(transparent)]
 pub struct StructuralParameters1(u32);
@@ -0,0 +1,72 @@
+//! The Command Ring.
+
+use super::trb::command::{Allowed, Link};
+use bit_field::BitField;
+
+/// The Command Ring.
+///
+/// Owns a single, DMA-allocated segment of TRBs and tracks the enqueue pointer and Producer
+/// Cycle State (PCS) needed to hand commands to the xHC. The final slot of the segment is
+/// reserved for a Link TRB that loops the ring back to its head, so `trbs` must hold at least
+/// one slot in addition to the Link TRB.
+pub struct CommandRing<'a> {
+    trbs: &'a mut [[u32; 4]],
+    ring_phys_addr: u64,
+    enqueue_index: usize,
+    cycle_state: bool,
+}
+impl<'a> CommandRing<'a> {
+    /// Creates a new `CommandRing` backed by `trbs`.
+    ///
+    /// `trbs` must be allocated in memory visible to the xHC via DMA, and `ring_phys_addr` must
+    /// be the physical (bus) address of `trbs[0]`. The last slot of `trbs` is overwritten with a
+    /// Link TRB that points back to `ring_phys_addr` with its Toggle Cycle bit set; callers must
+    /// not enqueue into that slot directly.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `trbs` holds fewer than 2 entries.
+    #[must_use]
+    pub fn new(trbs: &'a mut [[u32; 4]], ring_phys_addr: u64) -> Self {
+        assert!(
+            trbs.len() >= 2,
+            "A Command Ring segment must hold a Link TRB plus at least one command TRB."
+        );
+
+        let mut link = Link::default();
+        link.set_ring_segment_pointer(ring_phys_addr);
+        link.set_toggle_cycle(true);
+
+        let last = trbs.len() - 1;
+        trbs[last] = Allowed::Link(link).into_raw();
+
+        Self {
+            trbs,
+            ring_phys_addr,
+            enqueue_index: 0,
+            cycle_state: true,
+        }
+    }
+
+    /// Writes `trb` into the current slot with its Cycle bit forced to the ring's current
+    /// Producer Cycle State, then advances the enqueue pointer.
+    ///
+    /// Returns the physical address `trb` was written to. Compare this address against the TRB
+    /// Pointer of a Command Completion Event TRB to determine which command it completes.
+    pub fn enqueue(&mut self, trb: Allowed) -> u64 {
+        let mut raw = trb.into_raw();
+        raw[3].set_bit(0, self.cycle_state);
+
+        let addr = self.ring_phys_addr + (self.enqueue_index * 16) as u64;
+        self.trbs[self.enqueue_index] = raw;
+        self.enqueue_index += 1;
+
+        if self.enqueue_index == self.trbs.len() - 1 {
+            self.trbs[self.enqueue_index][3].set_bit(0, self.cycle_state);
+            self.cycle_state = !self.cycle_state;
+            self.enqueue_index = 0;
+        }
+
+        addr
+    }
+}
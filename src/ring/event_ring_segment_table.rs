@@ -0,0 +1,78 @@
+//! The Event Ring Segment Table.
+
+use bit_field::BitField;
+use core::convert::TryInto;
+
+/// An entry of the Event Ring Segment Table.
+///
+/// Each entry describes one segment of the Event Ring: its base address and the number of TRBs
+/// it holds. [`StructuralParameters2::event_ring_segment_table_max`] reports the maximum number
+/// of entries a table built from these may contain.
+///
+/// [`StructuralParameters2::event_ring_segment_table_max`]: crate::registers::capability::StructuralParameters2::event_ring_segment_table_max
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EventRingSegmentTableEntry {
+    base_address: u64,
+    segment_size: u32,
+    _reserved: u32,
+}
+impl EventRingSegmentTableEntry {
+    /// Creates a new entry describing a segment that starts at `base_address` and holds
+    /// `segment_size` TRBs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `base_address` is not 64-byte aligned, or if `segment_size` is not
+    /// in the range `16..=4096`.
+    #[must_use]
+    pub fn new(base_address: u64, segment_size: u16) -> Self {
+        let mut e = Self::default();
+        e.set_ring_segment_base_address(base_address);
+        e.set_ring_segment_size(segment_size);
+        e
+    }
+
+    /// Returns the value of the Ring Segment Base Address field.
+    #[must_use]
+    pub fn ring_segment_base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    /// Sets the value of the Ring Segment Base Address field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `a` is not 64-byte aligned.
+    pub fn set_ring_segment_base_address(&mut self, a: u64) -> &mut Self {
+        assert_eq!(
+            a % 64,
+            0,
+            "The Ring Segment Base Address must be 64-byte aligned."
+        );
+
+        self.base_address = a;
+        self
+    }
+
+    /// Returns the value of the Ring Segment Size field.
+    #[must_use]
+    pub fn ring_segment_size(&self) -> u16 {
+        self.segment_size.get_bits(0..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the Ring Segment Size field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `s` is not in the range `16..=4096`.
+    pub fn set_ring_segment_size(&mut self, s: u16) -> &mut Self {
+        assert!(
+            (16..=4096).contains(&s),
+            "The Ring Segment Size must be in the range 16..=4096."
+        );
+
+        self.segment_size = s.into();
+        self
+    }
+}
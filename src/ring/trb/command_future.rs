@@ -0,0 +1,210 @@
+//! Async command-completion support.
+//!
+//! This module is an alternative to busy-polling the Event Ring for a Command Completion Event:
+//! [`CommandFuture::new`] returns a future alongside a [`CommandNotifier`] handle, and the future
+//! resolves once the driver's interrupt handler calls [`CommandNotifier::notify`] with the
+//! observed event. This is gated behind the `alloc` feature, as it needs `alloc::sync::Arc` to
+//! share state between the future and the interrupt handler; the crate root must declare that
+//! feature and an `extern crate alloc;` for this module to take effect.
+
+#![cfg(feature = "alloc")]
+
+use alloc::sync::Arc;
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+/// The completion code and, for commands that allocate a Device Slot, the Slot ID produced by a
+/// Command Completion Event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandCompletion {
+    /// The value of the Completion Code field of the Command Completion Event TRB.
+    pub completion_code: u8,
+    /// The value of the Slot ID field of the Command Completion Event TRB.
+    ///
+    /// Only meaningful for commands that allocate a Device Slot, such as Enable Slot.
+    pub slot_id: u8,
+}
+
+/// No waker is registered, and no wake has been observed.
+const WAITING: usize = 0;
+/// `poll` is in the middle of registering a waker.
+const REGISTERING: usize = 0b01;
+/// `notify` has a completion to deliver.
+const WAKING: usize = 0b10;
+
+/// A lock-free slot for a single [`Waker`], following the arbitration scheme used by
+/// `futures::task::AtomicWaker`: a `poll` registering its waker and a `notify` delivering a wake
+/// never both touch the inner `Option<Waker>` at once, even though neither side blocks the other.
+struct WakerSlot {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+// SAFETY: `waker` is only ever touched by whichever of `register`/`take` wins the `state`
+// arbitration below, so at most one side accesses it at a time.
+unsafe impl Sync for WakerSlot {}
+impl WakerSlot {
+    const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Registers `waker` to be woken by a subsequent [`Self::take`].
+    fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) => {
+                // SAFETY: we just won the WAITING -> REGISTERING transition, so `take` cannot be
+                // touching `waker` until it observes us leave REGISTERING below.
+                unsafe {
+                    *self.waker.get() = Some(waker.clone());
+                }
+
+                if self
+                    .state
+                    .compare_exchange(REGISTERING, WAITING, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    // A `take` arrived while we were registering and set WAKING; the waker we
+                    // just stored may be the one it needs, so take it back and fire it now.
+                    let waker = unsafe { (*self.waker.get()).take() };
+                    self.state.store(WAITING, Ordering::Release);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            // Either a `take` is in progress (WAKING) or another `register` already owns the
+            // slot (REGISTERING); in both cases our registration is superseded.
+            Err(_) => {}
+        }
+    }
+
+    /// Takes the registered waker, if any and if no `register` is currently in progress.
+    fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            WAITING => {
+                // SAFETY: we observed WAITING and immediately set WAKING, so any concurrent
+                // `register` will see WAKING when it tries to hand the slot back and defer to
+                // us instead of touching `waker` again.
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            }
+            // A `register` is in progress; it will notice WAKING and deliver the wake itself.
+            _ => None,
+        }
+    }
+}
+
+/// State shared between a [`CommandFuture`] and its [`CommandNotifier`].
+struct Shared {
+    /// The physical address of the TRB this future is waiting on, as returned by
+    /// [`CommandRing::enqueue`](super::super::super::ring::command::CommandRing::enqueue).
+    trb_addr: u64,
+    ready: AtomicBool,
+    result: UnsafeCell<Option<CommandCompletion>>,
+    waker: WakerSlot,
+}
+// SAFETY: `result` is written by `notify` strictly before the Release-store to `ready`, and only
+// read by `poll` after an Acquire-load observes `ready`; `waker` arbitrates its own access.
+unsafe impl Sync for Shared {}
+
+/// A future that resolves when a matching Command Completion Event is observed.
+///
+/// Obtained from [`CommandFuture::new`] alongside a [`CommandNotifier`], which the driver's
+/// interrupt handler uses to deliver the completion.
+pub struct CommandFuture {
+    shared: Arc<Shared>,
+}
+impl CommandFuture {
+    /// Creates a new `CommandFuture` and the [`CommandNotifier`] used to resolve it.
+    ///
+    /// `trb_addr` is the physical address returned by
+    /// [`CommandRing::enqueue`](super::super::super::ring::command::CommandRing::enqueue) for
+    /// the command this future waits on. The returned future is typically `.await`ed by a task,
+    /// while the notifier is moved into the driver's interrupt handler.
+    #[must_use]
+    pub fn new(trb_addr: u64) -> (Self, CommandNotifier) {
+        let shared = Arc::new(Shared {
+            trb_addr,
+            ready: AtomicBool::new(false),
+            result: UnsafeCell::new(None),
+            waker: WakerSlot::new(),
+        });
+
+        (
+            Self {
+                shared: shared.clone(),
+            },
+            CommandNotifier { shared },
+        )
+    }
+}
+impl Future for CommandFuture {
+    type Output = CommandCompletion;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.shared.ready.load(Ordering::Acquire) {
+            // SAFETY: `ready` is set, so `notify` has finished writing `result` and will not
+            // touch it again for this command.
+            return Poll::Ready(unsafe { (*self.shared.result.get()).unwrap() });
+        }
+
+        self.shared.waker.register(cx.waker());
+
+        // Re-check in case `notify` ran between the first load and registering the waker above;
+        // `WakerSlot::register` already re-delivers the wake in that case, but we still need to
+        // report readiness to this call if it raced us.
+        if self.shared.ready.load(Ordering::Acquire) {
+            Poll::Ready(unsafe { (*self.shared.result.get()).unwrap() })
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// A cloneable handle used to resolve a [`CommandFuture`] from the driver's interrupt handler.
+///
+/// Cloning a `CommandNotifier` is cheap (it clones the underlying `Arc`) and every clone
+/// resolves the same future.
+#[derive(Clone)]
+pub struct CommandNotifier {
+    shared: Arc<Shared>,
+}
+impl CommandNotifier {
+    /// Called from the driver's interrupt handler when a Command Completion Event is observed.
+    ///
+    /// `event_trb_addr` is the TRB Pointer field of the event, and `completion` is the
+    /// completion code and Slot ID it carries. If `event_trb_addr` matches the command this
+    /// notifier was created for, the associated [`CommandFuture`] is resolved and its waker, if
+    /// any, is woken. Returns `true` if the event was consumed by this notifier.
+    pub fn notify(&self, event_trb_addr: u64, completion: CommandCompletion) -> bool {
+        if event_trb_addr != self.shared.trb_addr
+            || self.shared.ready.load(Ordering::Acquire)
+        {
+            return false;
+        }
+
+        // SAFETY: `ready` is not yet set, so `poll` has not read `result` yet, and only one
+        // event can match `trb_addr` for a given command.
+        unsafe {
+            *self.shared.result.get() = Some(completion);
+        }
+        self.shared.ready.store(true, Ordering::Release);
+
+        if let Some(waker) = self.shared.waker.take() {
+            waker.wake();
+        }
+
+        true
+    }
+}